@@ -0,0 +1,130 @@
+use axum::{
+    extract::rejection::{JsonRejection, QueryRejection},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+
+/// Errors returned by the API. Every variant has a fixed HTTP status and a
+/// stable `code` string, serialized as `{ "code": ..., "message": ... }`.
+/// Clients should match on `code`; `message` is for humans and may change.
+#[derive(Debug)]
+pub enum ApiError {
+    /// `UNAUTHORIZED` (401): no valid session cookie.
+    Unauthorized,
+    /// `INVALID_CREDENTIALS` (401): login with the wrong password.
+    InvalidCredentials,
+    /// `EVENT_NOT_FOUND` (404): no event has the given title.
+    EventNotFound(String),
+    /// `EVENT_BLOCKED` (409): the event is on hold and can't change status.
+    EventBlocked { title: String, reason: Option<String> },
+    /// `VALIDATION_FAILED` (400): malformed body, unknown field, or a value out of range.
+    ValidationFailed(String),
+    /// `UNSUPPORTED_MEDIA_TYPE` (415): the body isn't declared as JSON.
+    UnsupportedMediaType(String),
+    /// `RESPONSE_TOO_LARGE` (413): the result is too big to send; paginate instead.
+    ResponseTooLarge { size: usize, limit: usize },
+    /// `RATE_LIMITED` (429): too many mutating requests; retry after the given seconds.
+    RateLimited { retry_after_secs: u64 },
+    /// `MIRROR_NOT_CONFIGURED` (503): GitHub mirroring isn't set up.
+    MirrorNotConfigured,
+    /// `MIRROR_UNAVAILABLE` (502): GitHub couldn't be reached or returned bad data.
+    MirrorUnavailable(String),
+    /// `NOT_READY` (503): the service is up but can't serve events yet, e.g. events.json is unreadable.
+    NotReady(String),
+    /// `INTERNAL_ERROR` (500): a server-side failure, e.g. events.json couldn't be written.
+    /// The detail is logged, not returned.
+    Internal(String),
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    code: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::Unauthorized => "UNAUTHORIZED",
+            ApiError::InvalidCredentials => "INVALID_CREDENTIALS",
+            ApiError::EventNotFound(_) => "EVENT_NOT_FOUND",
+            ApiError::EventBlocked { .. } => "EVENT_BLOCKED",
+            ApiError::ValidationFailed(_) => "VALIDATION_FAILED",
+            ApiError::UnsupportedMediaType(_) => "UNSUPPORTED_MEDIA_TYPE",
+            ApiError::ResponseTooLarge { .. } => "RESPONSE_TOO_LARGE",
+            ApiError::RateLimited { .. } => "RATE_LIMITED",
+            ApiError::MirrorNotConfigured => "MIRROR_NOT_CONFIGURED",
+            ApiError::MirrorUnavailable(_) => "MIRROR_UNAVAILABLE",
+            ApiError::NotReady(_) => "NOT_READY",
+            ApiError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            ApiError::Unauthorized | ApiError::InvalidCredentials => StatusCode::UNAUTHORIZED,
+            ApiError::EventNotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::EventBlocked { .. } => StatusCode::CONFLICT,
+            ApiError::ValidationFailed(_) => StatusCode::BAD_REQUEST,
+            ApiError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ApiError::ResponseTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::MirrorNotConfigured | ApiError::NotReady(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::MirrorUnavailable(_) => StatusCode::BAD_GATEWAY,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            ApiError::Unauthorized => "Please Log In First".to_string(),
+            ApiError::InvalidCredentials => "Invalid Password".to_string(),
+            ApiError::EventNotFound(title) => format!("Event {} not found", title),
+            ApiError::EventBlocked { title, reason } => format!(
+                "Event {} is blocked: {}", title, reason.as_deref().unwrap_or("no reason given")
+            ),
+            ApiError::ValidationFailed(message)
+            | ApiError::UnsupportedMediaType(message)
+            | ApiError::MirrorUnavailable(message)
+            | ApiError::NotReady(message) => message.clone(),
+            ApiError::ResponseTooLarge { size, limit } => format!(
+                "Response would be {} bytes, over the {} byte limit; narrow the query or paginate with limit/offset",
+                size, limit
+            ),
+            ApiError::RateLimited { .. } => "Too many requests, slow down".to_string(),
+            ApiError::MirrorNotConfigured => "GitHub mirror is not configured".to_string(),
+            ApiError::Internal(_) => "Internal server error".to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if let ApiError::Internal(detail) = &self {
+            tracing::error!("Internal error: {}", detail);
+        }
+        let body = Json(ErrorBody { code: self.code(), message: self.message() });
+        match self {
+            ApiError::RateLimited { retry_after_secs } => {
+                (self.status(), [(header::RETRY_AFTER, retry_after_secs.to_string())], body).into_response()
+            }
+            _ => (self.status(), body).into_response(),
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        match rejection {
+            JsonRejection::MissingJsonContentType(_) => ApiError::UnsupportedMediaType(rejection.body_text()),
+            _ => ApiError::ValidationFailed(rejection.body_text()),
+        }
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        ApiError::ValidationFailed(rejection.body_text())
+    }
+}
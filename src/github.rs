@@ -0,0 +1,256 @@
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{env, time::Duration};
+use tokio::sync::{mpsc, oneshot};
+
+#[derive(Deserialize, Debug)]
+struct GitHubFileResponse { 
+    sha: String 
+}
+
+#[derive(Deserialize)]
+struct GitHubCommit {
+    commit: GitHubCommitDetail,
+}
+
+#[derive(Deserialize)]
+struct GitHubCommitDetail {
+    committer: GitHubCommitter,
+}
+
+#[derive(Deserialize)]
+struct GitHubCommitter {
+    date: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct GitHubUpdatePayload {
+    message: String,
+    content: String,
+    sha: String,
+}
+
+/// Handle to the background task that mirrors events.json to GitHub.
+/// Snapshots queued within the coalescing window are collapsed into a
+/// single push of the newest one.
+#[derive(Clone)]
+pub struct GitHubSync {
+    tx: mpsc::UnboundedSender<SyncMessage>,
+}
+
+enum SyncMessage {
+    Snapshot(String),
+    /// Push any pending snapshot without waiting out the window, then reply.
+    Flush(oneshot::Sender<()>),
+}
+
+impl GitHubSync {
+    /// Spawns the sync task. `GITHUB_COALESCE_MS` (default 2000) sets how
+    /// long to wait for further changes before pushing.
+    pub fn spawn() -> Self {
+        let window = env::var("GITHUB_COALESCE_MS")
+            .map(|v| v.parse().expect("GITHUB_COALESCE_MS must be a number of milliseconds"))
+            .unwrap_or(2000);
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(run_sync(rx, Duration::from_millis(window)));
+        GitHubSync { tx }
+    }
+
+    /// Queues the serialized events file for mirroring.
+    pub fn queue(&self, json_content: String) {
+        if self.tx.send(SyncMessage::Snapshot(json_content)).is_err() {
+            tracing::error!("GitHub sync task is not running; change was not mirrored");
+        }
+    }
+
+    /// Pushes the snapshot still waiting in the coalescing window, if any,
+    /// and returns once it has been pushed. Called on shutdown so the last
+    /// changes reach the mirror.
+    pub async fn flush(&self) {
+        let (done_tx, done_rx) = oneshot::channel();
+        if self.tx.send(SyncMessage::Flush(done_tx)).is_ok() {
+            let _ = done_rx.await;
+        }
+    }
+}
+
+async fn run_sync(mut rx: mpsc::UnboundedReceiver<SyncMessage>, window: Duration) {
+    while let Some(message) = rx.recv().await {
+        let mut latest = match message {
+            SyncMessage::Snapshot(json) => json,
+            // Nothing pending
+            SyncMessage::Flush(done) => {
+                let _ = done.send(());
+                continue;
+            }
+        };
+
+        let deadline = tokio::time::sleep(window);
+        tokio::pin!(deadline);
+
+        // Keep taking newer snapshots until the window closes, a flush is
+        // requested or every sender is gone
+        let mut flush = None;
+        loop {
+            tokio::select! {
+                _ = &mut deadline => break,
+                next = rx.recv() => match next {
+                    Some(SyncMessage::Snapshot(json)) => latest = json,
+                    Some(SyncMessage::Flush(done)) => {
+                        flush = Some(done);
+                        break;
+                    }
+                    None => break,
+                },
+            }
+        }
+
+        push_snapshot(latest).await;
+        if let Some(done) = flush {
+            let _ = done.send(());
+        }
+    }
+}
+
+async fn push_snapshot(json_content: String) {
+    if !is_configured() {
+        tracing::warn!("GITHUB_TOKEN, REPO_OWNER or REPO_NAME not set; skipping GitHub sync");
+        return;
+    }
+
+    if let Err(e) = push_to_github(json_content).await {
+        tracing::error!("Failed to sync with GitHub, mirror may be out of date until the next change: {}", e);
+    }
+}
+
+/// Contents API URL for the mirrored file plus the token to call it with.
+fn contents_endpoint() -> Result<(String, String), env::VarError> {
+    let token = env::var("GITHUB_TOKEN")?;
+    let owner = env::var("REPO_OWNER")?;
+    let repo = env::var("REPO_NAME")?;
+    let file_path = "events.json";
+    let url = format!("https://api.github.com/repos/{}/{}/contents/{}", owner, repo, file_path);
+    Ok((url, token))
+}
+
+pub fn is_configured() -> bool {
+    contents_endpoint().is_ok()
+}
+
+/// Downloads the mirrored events.json as raw text. The raw media type is
+/// used because the contents API omits inline content for files over 1 MB.
+pub async fn fetch_mirror() -> Result<String, Box<dyn std::error::Error>> {
+    let client = reqwest::Client::new();
+    let (url, token) = contents_endpoint()?;
+
+    let resp = client.get(&url)
+        .header("User-Agent", "rust-app")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Accept", "application/vnd.github.raw")
+        .send().await?
+        .error_for_status()?;
+
+    Ok(resp.text().await?)
+}
+
+/// Time of the most recent commit touching the mirrored file, if any.
+pub async fn mirror_last_updated() -> Result<Option<DateTime<Utc>>, Box<dyn std::error::Error>> {
+    let client = reqwest::Client::new();
+    let token = env::var("GITHUB_TOKEN")?;
+    let owner = env::var("REPO_OWNER")?;
+    let repo = env::var("REPO_NAME")?;
+    let url = format!("https://api.github.com/repos/{}/{}/commits?path=events.json&per_page=1", owner, repo);
+
+    let commits = client.get(&url)
+        .header("User-Agent", "rust-app")
+        .header("Authorization", format!("Bearer {}", token))
+        .send().await?
+        .error_for_status()?
+        .json::<Vec<GitHubCommit>>().await?;
+
+    Ok(commits.into_iter().next().map(|c| c.commit.committer.date))
+}
+
+/// Attempts per push before giving up; the wait doubles after each failure.
+const PUSH_ATTEMPTS: u32 = 4;
+const PUSH_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Why a single push attempt failed, and whether it is worth repeating.
+enum PushFailure {
+    /// Network error, 5xx, or 409 (the file changed since we read its sha).
+    Transient(String),
+    Permanent(String),
+}
+
+/// Pushes with retries. Each attempt re-reads the file's sha, so a 409 from
+/// a concurrent commit succeeds on the next try against the latest content.
+async fn push_to_github(json_content: String) -> Result<(), Box<dyn std::error::Error>> {
+    let client = reqwest::Client::new();
+    let (url, token) = contents_endpoint()?;
+    let encoded_content = general_purpose::STANDARD.encode(json_content);
+
+    let mut backoff = PUSH_INITIAL_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        match try_push(&client, &url, &token, &encoded_content).await {
+            Ok(()) => return Ok(()),
+            Err(PushFailure::Transient(reason)) if attempt < PUSH_ATTEMPTS => {
+                tracing::warn!("GitHub push attempt {}/{} failed: {}; retrying in {}s",
+                    attempt, PUSH_ATTEMPTS, reason, backoff.as_secs());
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            Err(PushFailure::Transient(reason)) => {
+                return Err(format!("gave up after {} attempts, last error: {}", attempt, reason).into());
+            }
+            Err(PushFailure::Permanent(reason)) => return Err(reason.into()),
+        }
+    }
+}
+
+async fn try_push(client: &reqwest::Client, url: &str, token: &str, encoded_content: &str) -> Result<(), PushFailure> {
+    let resp = client.get(url)
+        .header("User-Agent", "rust-app")
+        .header("Authorization", format!("Bearer {}", token))
+        .send().await
+        .map_err(|e| PushFailure::Transient(format!("reading current file: {}", e)))?;
+    let resp = check_status(resp, "reading current file").await?;
+    let current = resp.json::<GitHubFileResponse>().await
+        .map_err(|e| PushFailure::Permanent(format!("unexpected response reading current file: {}", e)))?;
+
+    let body = GitHubUpdatePayload {
+        message: "Update status via Web Dashboard".to_string(),
+        content: encoded_content.to_string(),
+        sha: current.sha,
+    };
+
+    let resp = client.put(url)
+        .header("User-Agent", "rust-app")
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .send().await
+        .map_err(|e| PushFailure::Transient(format!("updating file: {}", e)))?;
+    check_status(resp, "updating file").await?;
+
+    Ok(())
+}
+
+/// Passes successful responses through; otherwise classifies the status and
+/// includes GitHub's error body in the message.
+async fn check_status(resp: reqwest::Response, action: &str) -> Result<reqwest::Response, PushFailure> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+
+    let body = resp.text().await.unwrap_or_default();
+    let reason = format!("{} returned {}: {}", action, status, body.trim());
+    if status == reqwest::StatusCode::CONFLICT || status.is_server_error() {
+        Err(PushFailure::Transient(reason))
+    } else {
+        Err(PushFailure::Permanent(reason))
+    }
+}
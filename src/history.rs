@@ -0,0 +1,156 @@
+use crate::errors::ApiError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    net::IpAddr,
+};
+
+/// Append-only audit log of status changes, one JSON object per line.
+/// Kept next to events.json but not mirrored to GitHub.
+const HISTORY_PATH: &str = "events_history.jsonl";
+
+/// `new_status` of the entry recorded when an event is deleted. Entries up
+/// to and including it belong to the deleted event, not to any later event
+/// created with the same title.
+pub const DELETED: &str = "Deleted";
+
+/// One status transition of one event.
+#[derive(Serialize, Deserialize)]
+pub struct StatusChange {
+    pub title: String,
+    pub old_status: String,
+    pub new_status: String,
+    pub changed_at: DateTime<Utc>,
+    /// Client address of the admin session that made the change.
+    pub changed_by: IpAddr,
+}
+
+impl StatusChange {
+    pub fn new(title: &str, old_status: &str, new_status: &str, changed_by: IpAddr) -> Self {
+        StatusChange {
+            title: title.to_string(),
+            old_status: old_status.to_string(),
+            new_status: new_status.to_string(),
+            changed_at: Utc::now(),
+            changed_by,
+        }
+    }
+}
+
+/// Appends `changes` to the log in a single write. Returns the log's length
+/// before the write, so the caller can undo it with [`truncate_to`].
+pub fn append(changes: &[StatusChange]) -> Result<u64, ApiError> {
+    append_at(HISTORY_PATH, changes)
+}
+
+/// Cuts the log back to `len` bytes, dropping everything appended since.
+pub fn truncate_to(len: u64) -> io::Result<()> {
+    truncate_at(HISTORY_PATH, len)
+}
+
+/// Every recorded change to the current event titled `title`, oldest first.
+pub fn for_event(title: &str) -> Vec<StatusChange> {
+    for_event_at(HISTORY_PATH, title)
+}
+
+fn append_at(path: &str, changes: &[StatusChange]) -> Result<u64, ApiError> {
+    let mut lines = String::new();
+    for change in changes {
+        lines.push_str(&serde_json::to_string(change).unwrap());
+        lines.push('\n');
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| {
+            let len = file.metadata()?.len();
+            file.write_all(lines.as_bytes())?;
+            Ok(len)
+        })
+        .map_err(|e| ApiError::Internal(format!("Failed to append to {}: {}", path, e)))
+}
+
+fn truncate_at(path: &str, len: u64) -> io::Result<()> {
+    OpenOptions::new().write(true).open(path)?.set_len(len)
+}
+
+fn for_event_at(path: &str, title: &str) -> Vec<StatusChange> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let mut changes: Vec<StatusChange> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<StatusChange>(line).ok())
+        .filter(|change| change.title == title)
+        .collect();
+    if let Some(deleted_at) = changes.iter().rposition(|change| change.new_status == DELETED) {
+        changes.drain(..=deleted_at);
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    /// A log file in the temp dir, removed when dropped.
+    struct TempLog(String);
+
+    impl TempLog {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("{}-{}.jsonl", name, std::process::id()));
+            let _ = fs::remove_file(&path);
+            TempLog(path.to_string_lossy().into_owned())
+        }
+    }
+
+    impl Drop for TempLog {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn change(title: &str, new_status: &str) -> StatusChange {
+        StatusChange::new(title, "Not Yet Started", new_status, IpAddr::V4(Ipv4Addr::LOCALHOST))
+    }
+
+    #[test]
+    fn truncate_rolls_back_the_last_append() {
+        let log = TempLog::new("history-rollback");
+        append_at(&log.0, &[change("100", "Process Step 1")]).unwrap();
+
+        let len = append_at(&log.0, &[change("100", "Complete"), change("101", "Complete")]).unwrap();
+        assert_eq!(for_event_at(&log.0, "100").len(), 2);
+
+        truncate_at(&log.0, len).unwrap();
+        let remaining = for_event_at(&log.0, "100");
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].new_status, "Process Step 1");
+        assert!(for_event_at(&log.0, "101").is_empty());
+    }
+
+    #[test]
+    fn append_to_missing_log_starts_at_zero() {
+        let log = TempLog::new("history-new");
+        assert_eq!(append_at(&log.0, &[change("100", "Complete")]).unwrap(), 0);
+    }
+
+    #[test]
+    fn history_before_a_deletion_is_not_reused() {
+        let log = TempLog::new("history-deleted");
+        append_at(&log.0, &[change("100", "Complete"), change("101", "Complete")]).unwrap();
+        append_at(&log.0, &[change("100", DELETED)]).unwrap();
+        assert!(for_event_at(&log.0, "100").is_empty());
+
+        append_at(&log.0, &[change("100", "Process Step 1")]).unwrap();
+        let recreated = for_event_at(&log.0, "100");
+        assert_eq!(recreated.len(), 1);
+        assert_eq!(recreated[0].new_status, "Process Step 1");
+        assert_eq!(for_event_at(&log.0, "101").len(), 1);
+    }
+}
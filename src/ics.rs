@@ -0,0 +1,63 @@
+use crate::Event;
+use chrono::{Duration, NaiveDate, Utc};
+
+/// Renders events as an iCalendar feed with one all-day VEVENT per event.
+/// UIDs are derived from the title, so subscribers see updates rather than
+/// duplicates across fetches. Events with an unparseable date are skipped.
+pub fn render(events: &[Event]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//WIPAC//Pass3 Calendar//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Pass3 Runs".to_string(),
+    ];
+
+    for event in events {
+        let Ok(date) = NaiveDate::parse_from_str(&event.date, "%Y-%m-%d") else {
+            continue;
+        };
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:run-{}@pass3", escape(&event.title)));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+        lines.push(format!("DTEND;VALUE=DATE:{}", (date + Duration::days(1)).format("%Y%m%d")));
+        lines.push(format!("SUMMARY:Run {} - {}", escape(&event.title), escape(&event.status)));
+        lines.push(format!("DESCRIPTION:{}", escape(&event.description)));
+        if !event.url.is_empty() {
+            lines.push(format!("URL:{}", event.url));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+/// Escapes a TEXT value (RFC 5545 section 3.3.11).
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Folds a content line to at most 75 octets per physical line, never
+/// splitting a UTF-8 character.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            // The leading space of a continuation line counts toward its length
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
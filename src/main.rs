@@ -1,204 +1,1857 @@
 use axum::{
-    routing::{get, post},
+    routing::{get, patch, post},
     Router,
-    response::{Json, IntoResponse},
-    extract::Json as ExtractJson,
-    http::StatusCode,
+    response::{sse::{Event as SseEvent, KeepAlive, Sse}, Json, IntoResponse, Response},
+    async_trait,
+    body::{Body, Bytes, HttpBody, StreamBody},
+    extract::{rejection::{JsonRejection, QueryRejection}, ConnectInfo, FromRequest, FromRequestParts, Json as ExtractJson, Path, Query, State},
+    error_handling::HandleErrorLayer,
+    middleware,
+    BoxError,
+    http::{header, request::Parts, HeaderName, HeaderValue, Method, Request, StatusCode},
 };
-use std::{fs, net::SocketAddr, env};
+use std::{cmp::Ordering, collections::{BTreeMap, BTreeSet, HashMap}, convert::Infallible, fs, iter, net::{IpAddr, SocketAddr}, num::NonZeroUsize, env, sync::{Arc, Mutex}, time::{Duration, UNIX_EPOCH}};
 use serde::{Deserialize, Serialize};
-use tower_http::services::ServeFile;
-use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
+use tokio::sync::{broadcast, watch};
+use tokio_stream::wrappers::BroadcastStream;
+use futures_util::{stream, Stream, StreamExt};
+use tower::ServiceBuilder;
+use tower_http::{
+    compression::{
+        predicate::{NotForContentType, Predicate, SizeAbove},
+        CompressionLayer, CompressionLevel,
+    },
+    cors::CorsLayer, decompression::{DecompressionBody, RequestDecompressionLayer},
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    services::ServeFile, set_header::SetResponseHeaderLayer, timeout::TimeoutLayer,
+    trace::{DefaultOnResponse, TraceLayer},
+};
+use tracing::Level;
+use tracing_subscriber::EnvFilter;
 use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+
+mod errors;
+mod github;
+mod history;
+mod ics;
+mod rate_limit;
+mod sessions;
+mod webhook;
+use errors::ApiError;
+use github::GitHubSync;
+use history::StatusChange;
+use rate_limit::{limit_mutations, RateLimiter};
+use sessions::SessionStore;
 
 #[tokio::main]
 async fn main() {
-    // Require ADMIN_PASSWORD to be set - fail fast if not configured
-    let _admin_password = env::var("ADMIN_PASSWORD")
-        .expect("ADMIN_PASSWORD environment variable must be set");
+    // `--hash-password` reads a password from stdin and prints its ADMIN_PASSWORD_HASH
+    if env::args().nth(1).as_deref() == Some("--hash-password") {
+        let mut password = String::new();
+        std::io::stdin().read_line(&mut password).expect("Failed to read password from stdin");
+        println!("{}", hash_password(password.trim_end_matches(['\r', '\n'])));
+        return;
+    }
+
+    init_tracing();
+
+    // Require admin credentials - fail fast if not configured
+    match env::var("ADMIN_PASSWORD_HASH") {
+        Ok(hash) => {
+            PasswordHash::new(&hash).expect("ADMIN_PASSWORD_HASH must be an argon2 PHC string");
+        }
+        Err(_) => {
+            env::var("ADMIN_PASSWORD")
+                .expect("ADMIN_PASSWORD_HASH (or the deprecated ADMIN_PASSWORD) environment variable must be set");
+            tracing::warn!("ADMIN_PASSWORD is deprecated; set ADMIN_PASSWORD_HASH (generate it with --hash-password)");
+        }
+    }
+
+    // Flips to true once SIGINT/SIGTERM arrives; the drain, the deadline and open streams wait on it
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
+    let state = AppState {
+        github: GitHubSync::spawn(),
+        events_lock: Arc::new(Mutex::new(())),
+        // Admin sessions last SESSION_TTL_SECS (default 8 hours) from login
+        sessions: Arc::new(SessionStore::new(timeout_from_env("SESSION_TTL_SECS", 8 * 60 * 60))),
+        public_config: Arc::new(PublicConfig::from_env()),
+        max_batch_size: max_batch_size(),
+        max_response_bytes: max_response_bytes(),
+        status_updates: broadcast::channel(STATUS_UPDATE_BUFFER).0,
+        shutdown: shutdown_rx.clone(),
+    };
+
+    // Kept past `state` moving into the router: the mirror is flushed on
+    // shutdown and the rate limiter tells sessions apart
+    let github = state.github.clone();
+    let sessions = state.sessions.clone();
+
+    if env::var("GITHUB_SYNC_ON_STARTUP").map(|v| v == "true").unwrap_or(false) {
+        tokio::spawn(sync_mirror_on_startup(github.clone()));
+    }
+
+    // Routes that talk to GitHub or build large exports get a longer timeout
+    let slow_timeout = timeout_from_env("SLOW_REQUEST_TIMEOUT_SECS", 120);
 
     // FIX 1: Removed semicolon after the first route so the chain continues
     let app = Router::new()
         .route_service("/", ServeFile::new("index.html"))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
         .route("/api/events", get(get_events).post(update_event)) 
+        .route("/api/calendar.ics", get(get_calendar_feed))
         .route("/api/events/bulk", post(bulk_update_events))
-        .route("/api/login", post(login_handler)); 
+        .route("/api/events/change-token", get(get_change_token))
+        .route("/api/events/search", post(search_events))
+        .route("/api/events/recover-errors", post(recover_errors))
+        .route("/api/events/weekend", get(get_weekend_events))
+        .route("/api/events/overdue", get(get_overdue_events))
+        .route("/api/events/:title", patch(patch_event).delete(delete_event))
+        .route("/api/events/:title/history", get(get_event_history))
+        .route("/api/events/:title/siblings", get(get_event_siblings))
+        .route("/api/events/:title/block", post(block_event))
+        .route("/api/events/:title/unblock", post(unblock_event))
+        .route("/api/meta/states-in-use", get(get_states_in_use))
+        .route("/api/stats", get(get_status_counts))
+        .route("/api/stats/backlog", get(get_backlog))
+        .route("/api/stats/weekly", get(get_weekly_stats))
+        .route("/api/config", get(get_config))
+        .route("/api/login", post(login_handler))
+        .route("/api/logout", post(logout_handler))
+        .route("/api/me", get(me_handler))
+        // Layers only wrap routes added above, so slow routes below keep their own timeout
+        .layer(TimeoutLayer::new(timeout_from_env("REQUEST_TIMEOUT_SECS", 30)))
+        // Long-lived stream, so no timeout at all
+        .route("/api/events/stream", get(stream_status_updates))
+        .route("/api/github/diff", get(github_diff).layer(TimeoutLayer::new(slow_timeout)))
+        .route("/api/events.csv", get(export_events_csv).layer(TimeoutLayer::new(slow_timeout)));
+    let app = with_request_decompression(app).with_state(state);
+
+    // MUTATION_RATE_LIMIT is mutating requests per minute per client (admin
+    // session, or IP without one); 0 disables it
+    let mutation_limit = mutation_rate_limit();
+    let app = if mutation_limit > 0 {
+        let limiter = Arc::new(RateLimiter::per_minute(mutation_limit, sessions));
+        app.layer(middleware::from_fn_with_state(limiter, limit_mutations))
+    } else {
+        app
+    };
+
+    let app = app.layer(compression_layer());
+
+    let app = with_security_headers(app);
+
+    // Only attach CORS when cross-origin frontends are explicitly configured
+    let app = match cors_layer() {
+        Some(cors) => app.layer(cors),
+        None => app,
+    };
+
+    // Outermost: tag every request with an X-Request-Id (kept if the client sent one),
+    // log it with method, path, status and latency, and echo the id on the response
+    let app = app.layer(
+        ServiceBuilder::new()
+            .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+            .layer(
+                TraceLayer::new_for_http()
+                    .make_span_with(|req: &Request<_>| {
+                        let request_id = req.headers().get("x-request-id")
+                            .and_then(|id| id.to_str().ok())
+                            .unwrap_or_default();
+                        tracing::info_span!("request", method = %req.method(), path = %req.uri().path(), request_id)
+                    })
+                    .on_response(DefaultOnResponse::new().level(Level::INFO)),
+            )
+            .layer(PropagateRequestIdLayer::x_request_id()),
+    );
+
+    let addr = bind_addr();
+    log_startup_config(&addr);
+    tracing::info!("Listening on {}", addr);
+
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        let _ = shutdown_tx.send(true);
+    });
+
+    let mut drain_rx = shutdown_rx.clone();
+    let server = axum::Server::bind(&addr)
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(async move {
+            let _ = drain_rx.changed().await;
+            tracing::info!("Shutdown requested, draining in-flight requests");
+        });
+
+    let grace = timeout_from_env("SHUTDOWN_GRACE_SECS", 30);
+    let mut deadline_rx = shutdown_rx;
+    // Pending GitHub pushes are flushed within the same grace period as the drain
+    tokio::select! {
+        result = async {
+            let result = server.await;
+            github.flush().await;
+            result
+        } => result.unwrap(),
+        _ = async {
+            let _ = deadline_rx.changed().await;
+            tokio::time::sleep(grace).await;
+        } => {
+            tracing::warn!("Requests or GitHub sync still in flight after {}s shutdown grace period, exiting anyway", grace.as_secs());
+        }
+    }
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("Failed to listen for Ctrl+C");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+// --- CONFIG ---
+
+/// Methods `CORS_ALLOW_METHODS` may list. Anything else is almost certainly a
+/// typo, so it fails startup instead of becoming an extension method.
+const CORS_METHODS: [Method; 7] = [
+    Method::GET, Method::HEAD, Method::POST, Method::PUT, Method::PATCH, Method::DELETE, Method::OPTIONS,
+];
+
+/// Builds the CORS layer from `CORS_ALLOW_ORIGINS` (comma-separated).
+/// `CORS_ALLOW_METHODS` overrides the default GET/POST/PATCH/DELETE set and
+/// `CORS_MAX_AGE` (seconds) lets browsers cache preflight responses.
+fn cors_layer() -> Option<CorsLayer> {
+    let origins = env::var("CORS_ALLOW_ORIGINS").ok()?;
+    let origins: Vec<HeaderValue> = origins
+        .split(',')
+        .map(str::trim)
+        .filter(|o| !o.is_empty())
+        .map(|o| o.parse().unwrap_or_else(|_| panic!("Invalid origin in CORS_ALLOW_ORIGINS: {}", o)))
+        .collect();
+
+    let methods: Vec<Method> = match env::var("CORS_ALLOW_METHODS") {
+        Ok(list) => list
+            .split(',')
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .map(|m| CORS_METHODS.iter()
+                .find(|known| known.as_str().eq_ignore_ascii_case(m))
+                .cloned()
+                .unwrap_or_else(|| panic!("Invalid method in CORS_ALLOW_METHODS: {} (expected one of GET, HEAD, POST, PUT, PATCH, DELETE, OPTIONS)", m)))
+            .collect(),
+        Err(_) => vec![Method::GET, Method::POST, Method::PATCH, Method::DELETE],
+    };
+
+    let mut cors = CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(methods)
+        .allow_headers([header::CONTENT_TYPE])
+        .expose_headers([TOTAL_COUNT_HEADER, HeaderName::from_static("x-request-id")])
+        .allow_credentials(true);
+
+    if let Ok(max_age) = env::var("CORS_MAX_AGE") {
+        let secs: u64 = max_age.parse().expect("CORS_MAX_AGE must be a number of seconds");
+        cors = cors.max_age(Duration::from_secs(secs));
+    }
+
+    Some(cors)
+}
+
+/// Accepts gzip-compressed request bodies (`Content-Encoding: gzip`) for large
+/// bulk payloads. The JSON extractors' body limit applies to the inflated size.
+/// Bodies that don't inflate are 400 `VALIDATION_FAILED`; any other encoding is
+/// 415 `UNSUPPORTED_MEDIA_TYPE`.
+fn with_request_decompression<S>(app: Router<S, DecompressionBody<Body>>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    app.layer(
+        ServiceBuilder::new()
+            .layer(middleware::map_response(unsupported_encoding_as_api_error))
+            .layer(HandleErrorLayer::new(|err: BoxError| async move {
+                ApiError::ValidationFailed(format!("Could not decode request body: {}", err))
+            }))
+            .layer(RequestDecompressionLayer::new()),
+    )
+}
+
+/// `RequestDecompressionLayer` answers an unsupported `Content-Encoding` with
+/// an empty 415 carrying `Accept-Encoding`; give it the usual error body.
+async fn unsupported_encoding_as_api_error<B>(response: Response<B>) -> Response
+where
+    B: HttpBody<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    if response.status() != StatusCode::UNSUPPORTED_MEDIA_TYPE || response.headers().contains_key(header::CONTENT_TYPE) {
+        return response.into_response();
+    }
+    let accepted = response.headers().get(header::ACCEPT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("identity")
+        .to_string();
+    ApiError::UnsupportedMediaType(format!("Unsupported Content-Encoding; accepted encodings: {}", accepted)).into_response()
+}
+
+// Allows the inline script/styles in index.html and the FullCalendar CDN bundle.
+const DEFAULT_CSP: &str = "default-src 'self'; \
+    script-src 'self' 'unsafe-inline' https://cdn.jsdelivr.net; \
+    style-src 'self' 'unsafe-inline' https://cdn.jsdelivr.net; \
+    img-src 'self' data:; connect-src 'self'; frame-ancestors 'none'";
+
+fn timeout_from_env(key: &str, default_secs: u64) -> Duration {
+    let secs = env::var(key)
+        .map(|v| v.parse().unwrap_or_else(|_| panic!("{} must be a number of seconds", key)))
+        .unwrap_or(default_secs);
+    Duration::from_secs(secs)
+}
+
+/// Gzip responses of at least `COMPRESSION_MIN_SIZE` bytes (default 1024)
+/// at `COMPRESSION_LEVEL` (1-9, default is the gzip default).
+fn compression_layer() -> CompressionLayer<impl Predicate> {
+    let min_size: u16 = env::var("COMPRESSION_MIN_SIZE")
+        .map(|v| v.parse().expect("COMPRESSION_MIN_SIZE must be a number of bytes (max 65535)"))
+        .unwrap_or(1024);
+    let level = match env::var("COMPRESSION_LEVEL") {
+        Ok(v) => match v.parse::<u32>() {
+            Ok(n @ 1..=9) => CompressionLevel::Precise(n),
+            _ => panic!("COMPRESSION_LEVEL must be between 1 and 9"),
+        },
+        Err(_) => CompressionLevel::Default,
+    };
+
+    CompressionLayer::new()
+        .quality(level)
+        .compress_when(
+            SizeAbove::new(min_size)
+                .and(NotForContentType::IMAGES)
+                // Gzip would buffer server-sent events instead of delivering them immediately
+                .and(NotForContentType::const_new("text/event-stream")),
+        )
+}
+
+/// Logs via `tracing`, filtered by `RUST_LOG` (full directive syntax) or
+/// else `LOG_LEVEL` (e.g. `debug`), default `info`.
+fn init_tracing() {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string())));
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+}
+
+/// Address to listen on, from `BIND_ADDR` (default 0.0.0.0) and `PORT` (default 8080).
+fn bind_addr() -> SocketAddr {
+    let ip: IpAddr = env::var("BIND_ADDR")
+        .map(|v| v.parse().unwrap_or_else(|_| panic!("BIND_ADDR must be an IP address, got {:?}", v)))
+        .unwrap_or(IpAddr::from([0, 0, 0, 0]));
+    let port: u16 = env::var("PORT")
+        .map(|v| v.parse().unwrap_or_else(|_| panic!("PORT must be a number from 0 to 65535, got {:?}", v)))
+        .unwrap_or(8080);
+    SocketAddr::new(ip, port)
+}
+
+/// Upper bound on titles accepted by batch endpoints (`MAX_BATCH_SIZE`, default 500).
+fn max_batch_size() -> usize {
+    env::var("MAX_BATCH_SIZE")
+        .map(|v| v.parse::<NonZeroUsize>().expect("MAX_BATCH_SIZE must be a positive number").get())
+        .unwrap_or(500)
+}
+
+/// Largest JSON body list endpoints will send (`MAX_RESPONSE_BYTES`, default 8 MiB).
+fn max_response_bytes() -> usize {
+    env::var("MAX_RESPONSE_BYTES")
+        .map(|v| v.parse().expect("MAX_RESPONSE_BYTES must be a number of bytes"))
+        .unwrap_or(8 * 1024 * 1024)
+}
+
+fn mutation_rate_limit() -> u32 {
+    env::var("MUTATION_RATE_LIMIT")
+        .map(|v| v.parse().expect("MUTATION_RATE_LIMIT must be a number of requests per minute"))
+        .unwrap_or(60)
+}
+
+fn security_headers_enabled() -> bool {
+    env::var("SECURITY_HEADERS").map(|v| v != "false").unwrap_or(true)
+}
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], 80));
-    println!("Listening on {}", addr);
+/// Adds baseline security headers to every response (API and served HTML).
+/// Set `SECURITY_HEADERS=false` to disable, or `CONTENT_SECURITY_POLICY` to
+/// replace the default policy.
+fn with_security_headers(app: Router) -> Router {
+    if !security_headers_enabled() {
+        return app;
+    }
+
+    let csp = env::var("CONTENT_SECURITY_POLICY").unwrap_or_else(|_| DEFAULT_CSP.to_string());
+    let csp = HeaderValue::from_str(&csp).expect("CONTENT_SECURITY_POLICY is not a valid header value");
 
-    axum::Server::bind(&addr)
-        .serve(app.into_make_service())
-        .await
-        .unwrap();
+    let headers = [
+        (header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff")),
+        (header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY")),
+        (header::REFERRER_POLICY, HeaderValue::from_static("strict-origin-when-cross-origin")),
+        (header::CONTENT_SECURITY_POLICY, csp),
+    ];
+
+    headers.into_iter().fold(app, |app, (name, value): (HeaderName, HeaderValue)| {
+        app.layer(SetResponseHeaderLayer::if_not_present(name, value))
+    })
+}
+
+/// Logs the resolved configuration once at startup as a single event with
+/// one field per setting. Secrets are only reported as set/unset, never echoed.
+fn log_startup_config(addr: &SocketAddr) {
+    let admin_password = if env::var("ADMIN_PASSWORD_HASH").is_ok() {
+        "argon2 hash"
+    } else {
+        "plaintext (deprecated)"
+    };
+    let github_mirror = match (env::var("REPO_OWNER"), env::var("REPO_NAME"), env::var("GITHUB_TOKEN")) {
+        (Ok(owner), Ok(repo), Ok(_)) => format!("{}/{}", owner, repo),
+        _ => "disabled (GITHUB_TOKEN/REPO_OWNER/REPO_NAME not all set)".to_string(),
+    };
+    let mutation_limit = match mutation_rate_limit() {
+        0 => "disabled".to_string(),
+        n => format!("{}/min per session or IP", n),
+    };
+
+    tracing::info!(
+        bind_addr = %addr,
+        events_file = "events.json",
+        admin_password,
+        session_ttl_secs = timeout_from_env("SESSION_TTL_SECS", 8 * 60 * 60).as_secs(),
+        github_mirror = %github_mirror,
+        github_sync_on_startup = %env::var("GITHUB_SYNC_ON_STARTUP").unwrap_or_else(|_| "false".to_string()),
+        github_coalesce_ms = %env::var("GITHUB_COALESCE_MS").unwrap_or_else(|_| "2000".to_string()),
+        error_webhook = env::var("ERROR_WEBHOOK_URL").is_ok(),
+        cors_origins = %env::var("CORS_ALLOW_ORIGINS").unwrap_or_else(|_| "disabled".to_string()),
+        shutdown_grace_secs = timeout_from_env("SHUTDOWN_GRACE_SECS", 30).as_secs(),
+        max_batch_size = max_batch_size(),
+        max_response_bytes = max_response_bytes(),
+        request_timeout_secs = timeout_from_env("REQUEST_TIMEOUT_SECS", 30).as_secs(),
+        slow_request_timeout_secs = timeout_from_env("SLOW_REQUEST_TIMEOUT_SECS", 120).as_secs(),
+        mutation_limit = %mutation_limit,
+        security_headers = security_headers_enabled(),
+        "Startup configuration"
+    );
 }
 
 // --- DATA STRUCTURES ---
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Clone)]
+struct AppState {
+    // Live feed for /api/events/stream; sending with no subscribers is a no-op
+    status_updates: broadcast::Sender<StatusUpdate>,
+    // Becomes true on SIGINT/SIGTERM so long-lived streams end and shutdown can drain
+    shutdown: watch::Receiver<bool>,
+    github: GitHubSync,
+    // Held across every read-modify-write of events.json and its history
+    // append, so concurrent admin edits can't overwrite each other
+    events_lock: Arc<Mutex<()>>,
+    sessions: Arc<SessionStore>,
+    public_config: Arc<PublicConfig>,
+    max_batch_size: usize,
+    max_response_bytes: usize,
+}
+
+/// Non-sensitive settings the frontend adapts to. Never add secrets here.
+#[derive(Serialize, Clone)]
+struct PublicConfig {
+    github_mirror_enabled: bool,
+    workflow_statuses: [&'static str; 10],
+    mutation_rate_limit_per_minute: u32,
+    max_batch_size: usize,
+}
+
+impl PublicConfig {
+    fn from_env() -> Self {
+        PublicConfig {
+            github_mirror_enabled: github::is_configured(),
+            workflow_statuses: WORKFLOW_STATUSES,
+            mutation_rate_limit_per_minute: mutation_rate_limit(),
+            max_batch_size: max_batch_size(),
+        }
+    }
+}
+
+/// Workflow statuses in pipeline order, matching the options offered by index.html.
+const WORKFLOW_STATUSES: [&str; 10] = [
+    "Not Yet Started",
+    "Transfer from Tape",
+    "Process Step 1",
+    "Finish Step 1",
+    "Transfer WIPAC",
+    "Process Step 2",
+    "Finish Step 2",
+    "Complete",
+    "Step 1 Error",
+    "Step 2 Error",
+];
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 struct Event {
     title: String,
     date: String,
     url: String,
     status: String,
     description: String,
+    // Operator hold, independent of the workflow status. Omitted from the JSON when unset.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    blocked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    blocked_reason: Option<String>,
+    // Date processing is committed to finish by; see /api/events/overdue.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deadline: Option<NaiveDate>,
+}
+
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+enum SortKey {
+    #[default]
+    Date,
+    Title,
+    Status,
+}
+
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
 }
 
 #[derive(Deserialize)]
+struct EventsQuery {
+    #[serde(default)]
+    sort: SortKey,
+    #[serde(default)]
+    order: SortOrder,
+    status: Option<String>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct ExportQuery {
+    status: Option<String>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+#[derive(Deserialize)]
+struct CalendarQuery {
+    status: Option<String>,
+}
+
+#[derive(Serialize)]
+struct StatusMismatch {
+    title: String,
+    local_status: String,
+    github_status: String,
+}
+
+#[derive(Serialize)]
+struct MirrorDiff {
+    only_local: Vec<String>,
+    only_github: Vec<String>,
+    status_mismatched: Vec<StatusMismatch>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 struct UpdatePayload {
     title: String,
     new_status: String,
 }
 
 #[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
 struct BulkUpdatePayload {
     titles: Vec<String>,
     new_status: String,
 }
 
+/// Fields an admin can correct after import. Omitted or null fields are left unchanged.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EventPatch {
+    date: Option<NaiveDate>,
+    url: Option<String>,
+    description: Option<String>,
+    deadline: Option<NaiveDate>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlockPayload {
+    reason: Option<String>,
+}
+
 #[derive(Deserialize)] 
+#[serde(deny_unknown_fields)]
 struct LoginPayload {
     password: String,
 }
 
-#[derive(Deserialize, Debug)]
-struct GitHubFileResponse { 
-    sha: String 
+/// Criteria shared by endpoints that select a subset of events. All fields
+/// are optional and combine with AND.
+#[derive(Default)]
+struct EventFilter {
+    /// Matches a title prefix or text anywhere in the description (case-insensitive).
+    q: Option<String>,
+    /// Matches any of the listed statuses.
+    statuses: Option<Vec<String>>,
+    /// Inclusive date bounds (YYYY-MM-DD).
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+impl EventFilter {
+    fn matches(&self, event: &Event) -> bool {
+        if let Some(q) = &self.q {
+            let q = q.to_lowercase();
+            if !event.title.to_lowercase().starts_with(&q) && !event.description.to_lowercase().contains(&q) {
+                return false;
+            }
+        }
+        if self.statuses.as_ref().is_some_and(|statuses| !statuses.contains(&event.status)) {
+            return false;
+        }
+        if self.from.is_some() || self.to.is_some() {
+            let Ok(date) = NaiveDate::parse_from_str(&event.date, "%Y-%m-%d") else {
+                return false;
+            };
+            if self.from.is_some_and(|from| date < from) || self.to.is_some_and(|to| date > to) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Body of `POST /api/events/search`: the `EventFilter` criteria plus paging.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SearchPayload {
+    q: Option<String>,
+    statuses: Option<Vec<String>>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct WeekendQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct EventPage {
+    events: Vec<Event>,
+    total: usize,
+}
+
+#[derive(Serialize)]
+struct OverdueEvent {
+    #[serde(flatten)]
+    event: Event,
+    days_overdue: i64,
 }
 
 #[derive(Serialize)]
-struct GitHubUpdatePayload {
-    message: String,
-    content: String,
-    sha: String,
+struct EventRef {
+    title: String,
+    status: String,
+}
+
+#[derive(Serialize)]
+struct Siblings {
+    previous: Option<EventRef>,
+    next: Option<EventRef>,
+}
+
+#[derive(Deserialize)]
+struct WeeklyQuery {
+    weeks: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct WeekStats {
+    week_start: NaiveDate,
+    iso_week: String,
+    started: usize,
+    currently_complete: usize,
+}
+
+#[derive(Deserialize)]
+struct StatsQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+#[derive(Serialize)]
+struct StatusCounts {
+    total: usize,
+    counts: BTreeMap<String, usize>,
+}
+
+#[derive(Serialize)]
+struct Backlog {
+    total: usize,
+    in_error: usize,
+    not_in_error: usize,
+}
+
+#[derive(Serialize)]
+struct SessionInfo {
+    authenticated: bool,
+    role: Option<String>,
+    username: Option<String>,
+}
+
+/// Pushed to `/api/events/stream` subscribers on every status change.
+#[derive(Serialize, Clone)]
+struct StatusUpdate {
+    title: String,
+    new_status: String,
+}
+
+#[derive(Serialize)]
+struct BulkUpdateResult {
+    updated: usize,
+    not_found: Vec<String>,
+    blocked: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct RecoverResult {
+    recovered: usize,
+}
+
+#[derive(Serialize)]
+struct ChangeToken {
+    token: String,
+}
+
+// --- EXTRACTORS ---
+
+/// JSON body extractor that reports bad payloads (malformed JSON, unknown or
+/// missing fields) as a 400 `ApiError` rather than axum's default 422.
+struct JsonBody<T>(T);
+
+#[async_trait]
+impl<T, S, B> FromRequest<S, B> for JsonBody<T>
+where
+    ExtractJson<T>: FromRequest<S, B, Rejection = JsonRejection>,
+    S: Send + Sync,
+    B: Send + 'static,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request<B>, state: &S) -> Result<Self, Self::Rejection> {
+        let ExtractJson(value) = ExtractJson::<T>::from_request(req, state).await?;
+        Ok(JsonBody(value))
+    }
+}
+
+/// Query string extractor that reports bad parameters (unknown enum values,
+/// unparseable numbers or dates) as a 400 `ApiError` like `JsonBody` does.
+struct QueryParams<T>(T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for QueryParams<T>
+where
+    Query<T>: FromRequestParts<S, Rejection = QueryRejection>,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state).await?;
+        Ok(QueryParams(value))
+    }
+}
+
+// --- STARTUP ---
+
+/// Re-pushes events.json once if it differs from the GitHub mirror and was
+/// modified after the mirror's last commit, healing drift from downtime.
+async fn sync_mirror_on_startup(github: GitHubSync) {
+    if !github::is_configured() {
+        tracing::info!("Startup mirror sync skipped: GitHub mirror is not configured");
+        return;
+    }
+
+    let path = "events.json";
+    // load_events would turn a missing or corrupt file into [] and wipe the mirror
+    let local = match read_events(path) {
+        Ok(events) => events,
+        Err(e) => {
+            tracing::warn!("Startup mirror sync skipped: {}", e);
+            return;
+        }
+    };
+
+    let remote = match github::fetch_mirror().await {
+        Ok(body) => serde_json::from_str::<Vec<Event>>(&body).ok(),
+        Err(e) => {
+            tracing::warn!("Startup mirror sync failed to fetch GitHub mirror: {}", e);
+            return;
+        }
+    };
+    if remote.as_ref() == Some(&local) {
+        tracing::info!("Startup mirror sync: GitHub mirror is up to date");
+        return;
+    }
+
+    let local_modified: Option<DateTime<Utc>> = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(DateTime::from);
+    let mirror_updated = match github::mirror_last_updated().await {
+        Ok(updated) => updated,
+        Err(e) => {
+            tracing::warn!("Startup mirror sync failed to read mirror history: {}", e);
+            return;
+        }
+    };
+
+    match (local_modified, mirror_updated) {
+        (Some(local), Some(mirror)) if local <= mirror => {
+            tracing::info!("Startup mirror sync: mirror differs but is newer than {}; not pushing", path);
+        }
+        _ => {
+            tracing::info!("Startup mirror sync: {} is newer than the mirror, pushing", path);
+            github.queue(serde_json::to_string_pretty(&local).unwrap());
+        }
+    }
 }
 
 // --- HANDLERS ---
 
-async fn get_events() -> Json<Vec<Event>> {
-    let data = fs::read_to_string("events.json").unwrap_or_else(|_| "[]".to_string());
-    let events: Vec<Event> = serde_json::from_str(&data).unwrap_or(vec![]);
-    Json(events)
+/// How many status updates a slow `/api/events/stream` client can fall
+/// behind before it starts skipping them.
+const STATUS_UPDATE_BUFFER: usize = 256;
+
+/// Server-sent events: a `status` event with `{ title, new_status }` for
+/// every status change. Clients that lag skip missed updates rather than
+/// holding up writers. The stream ends on shutdown so the server can drain.
+async fn stream_status_updates(
+    State(state): State<AppState>
+) -> Sse<impl Stream<Item = Result<SseEvent, serde_json::Error>>> {
+    let mut shutdown = state.shutdown.clone();
+    let updates = BroadcastStream::new(state.status_updates.subscribe())
+        .filter_map(|update| async move { update.ok() })
+        .map(|update| SseEvent::default().event("status").json_data(update))
+        .take_until(async move {
+            let _ = shutdown.wait_for(|&down| down).await;
+        });
+    Sse::new(updates).keep_alive(KeepAlive::default())
+}
+
+fn publish_status_changes(state: &AppState, changes: &[StatusChange]) {
+    for change in changes {
+        let _ = state.status_updates.send(StatusUpdate {
+            title: change.title.clone(),
+            new_status: change.new_status.clone(),
+        });
+    }
+}
+
+/// Liveness probe: 200 whenever the process is serving requests.
+async fn healthz() -> Json<&'static str> {
+    Json("ok")
+}
+
+/// Readiness probe: 200 only if events.json can be read and parsed. Other
+/// endpoints treat an unreadable file as empty, so this is where it shows up.
+async fn readyz() -> Result<Json<&'static str>, ApiError> {
+    read_events("events.json").map_err(ApiError::NotReady)?;
+    Ok(Json("ready"))
+}
+
+/// Lists events sorted by `?sort=date|title|status` (default `date`) and
+/// `?order=asc|desc` (default `desc`). Ties always fall back to the title
+/// so paging through the list is deterministic.
+///
+/// `?status=`, `?from=` and `?to=` (inclusive, YYYY-MM-DD) filter the list,
+/// and `?limit=` (default 100, max 1000) / `?offset=` page it. The body stays
+/// a plain array; the filtered total is in the `X-Total-Count` header.
+async fn get_events(State(state): State<AppState>, QueryParams(query): QueryParams<EventsQuery>) -> Result<Response, ApiError> {
+    if let Some(status) = &query.status {
+        validate_status(status)?;
+    }
+    let filter = EventFilter {
+        statuses: query.status.map(|s| vec![s]),
+        from: query.from,
+        to: query.to,
+        ..Default::default()
+    };
+
+    let mut events = load_events("events.json");
+    events.retain(|e| filter.matches(e));
+    sort_events(&mut events, query.sort, query.order);
+
+    let page = paginate(events, query.limit, query.offset)?;
+    let mut response = bounded_json(&page.events, state.max_response_bytes);
+    if response.status().is_success() {
+        response.headers_mut().insert(TOTAL_COUNT_HEADER, HeaderValue::from(page.total));
+    }
+    Ok(response)
+}
+
+/// iCalendar feed of events for Google Calendar/Outlook subscriptions,
+/// optionally limited to one `?status=`.
+async fn get_calendar_feed(QueryParams(query): QueryParams<CalendarQuery>) -> Result<Response, ApiError> {
+    if let Some(status) = &query.status {
+        validate_status(status)?;
+    }
+
+    let mut events = load_events("events.json");
+    events.retain(|e| query.status.as_ref().is_none_or(|status| &e.status == status));
+    sort_events(&mut events, SortKey::Date, SortOrder::Asc);
+
+    Ok(([(header::CONTENT_TYPE, "text/calendar; charset=utf-8")], ics::render(&events)).into_response())
+}
+
+/// Spreadsheet export of events, oldest first, with the same `?status=`,
+/// `?from=` and `?to=` filters as the JSON list.
+async fn export_events_csv(QueryParams(query): QueryParams<ExportQuery>) -> Result<Response, ApiError> {
+    if let Some(status) = &query.status {
+        validate_status(status)?;
+    }
+    let filter = EventFilter {
+        statuses: query.status.map(|s| vec![s]),
+        from: query.from,
+        to: query.to,
+        ..Default::default()
+    };
+
+    let mut events = load_events("events.json");
+    events.retain(|e| filter.matches(e));
+    sort_events(&mut events, SortKey::Date, SortOrder::Asc);
+
+    // One chunk per row, so the whole file is never held as a single string
+    let header = iter::once("title,date,status,url,description,blocked\r\n".to_string());
+    let rows = events.into_iter().map(|e| csv_row(&e));
+    let body = StreamBody::new(stream::iter(header.chain(rows).map(Ok::<_, Infallible>)));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"events.csv\""),
+        ],
+        body,
+    ).into_response())
+}
+
+fn csv_row(e: &Event) -> String {
+    let row = [e.title.as_str(), &e.date, &e.status, &e.url, &e.description, if e.blocked { "true" } else { "false" }];
+    row.map(csv_field).join(",") + "\r\n"
+}
+
+/// Quotes a CSV field if it contains a delimiter, quote or line break (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn sort_events(events: &mut [Event], key: SortKey, order: SortOrder) {
+    let status_rank = |status: &str| {
+        WORKFLOW_STATUSES.iter().position(|s| *s == status).unwrap_or(WORKFLOW_STATUSES.len())
+    };
+
+    events.sort_by(|a, b| {
+        let primary = match key {
+            SortKey::Date => a.date.cmp(&b.date),
+            SortKey::Title => Ordering::Equal,
+            SortKey::Status => status_rank(&a.status).cmp(&status_rank(&b.status)),
+        };
+        let ordering = primary.then_with(|| compare_titles(&a.title, &b.title));
+        match order {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    });
+}
+
+/// Titles are run numbers, so compare them numerically when both parse.
+fn compare_titles(a: &str, b: &str) -> Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+const DEFAULT_PAGE_SIZE: usize = 100;
+const TOTAL_COUNT_HEADER: HeaderName = HeaderName::from_static("x-total-count");
+const MAX_PAGE_SIZE: usize = 1000;
+
+/// Combined free-text and field search, newest first, paginated.
+async fn search_events(
+    State(state): State<AppState>,
+    JsonBody(payload): JsonBody<SearchPayload>
+) -> Result<Response, ApiError> {
+    for status in payload.statuses.iter().flatten() {
+        validate_status(status)?;
+    }
+    let filter = EventFilter {
+        q: payload.q,
+        statuses: payload.statuses,
+        from: payload.from,
+        to: payload.to,
+    };
+
+    let mut events = load_events("events.json");
+    events.retain(|e| filter.matches(e));
+    sort_events(&mut events, SortKey::Date, SortOrder::Desc);
+
+    let page = paginate(events, payload.limit, payload.offset)?;
+    Ok(bounded_json(&page, state.max_response_bytes))
+}
+
+/// Events whose date falls on a Saturday or Sunday, newest first, paginated.
+async fn get_weekend_events(
+    State(state): State<AppState>,
+    QueryParams(query): QueryParams<WeekendQuery>
+) -> Result<Response, ApiError> {
+    let filter = EventFilter { from: query.from, to: query.to, ..Default::default() };
+
+    let mut events = load_events("events.json");
+    events.retain(|e| {
+        filter.matches(e)
+            && NaiveDate::parse_from_str(&e.date, "%Y-%m-%d")
+                .is_ok_and(|d| matches!(d.weekday(), Weekday::Sat | Weekday::Sun))
+    });
+    sort_events(&mut events, SortKey::Date, SortOrder::Desc);
+
+    let page = paginate(events, query.limit, query.offset)?;
+    Ok(bounded_json(&page, state.max_response_bytes))
+}
+
+/// Events that aren't Complete and whose deadline has passed, most overdue first.
+async fn get_overdue_events(State(state): State<AppState>) -> Response {
+    let overdue = overdue_events(load_events("events.json"), Utc::now().date_naive());
+    bounded_json(&overdue, state.max_response_bytes)
+}
+
+fn overdue_events(events: Vec<Event>, today: NaiveDate) -> Vec<OverdueEvent> {
+    let mut overdue: Vec<OverdueEvent> = events
+        .into_iter()
+        .filter(|e| e.status != "Complete")
+        .filter_map(|event| {
+            let days_overdue = (today - event.deadline?).num_days();
+            (days_overdue > 0).then_some(OverdueEvent { event, days_overdue })
+        })
+        .collect();
+    overdue.sort_by_key(|o| std::cmp::Reverse(o.days_overdue));
+    overdue
+}
+
+/// Serializes a list response, refusing with `RESPONSE_TOO_LARGE` instead of sending a body
+/// larger than `max_bytes`.
+fn bounded_json<T: Serialize>(value: &T, max_bytes: usize) -> Response {
+    let body = serde_json::to_vec(value).unwrap();
+    if body.len() > max_bytes {
+        return ApiError::ResponseTooLarge { size: body.len(), limit: max_bytes }.into_response();
+    }
+    ([(header::CONTENT_TYPE, "application/json")], body).into_response()
+}
+
+/// Windows already filtered and sorted events, keeping the pre-paging total.
+fn paginate(events: Vec<Event>, limit: Option<usize>, offset: Option<usize>) -> Result<EventPage, ApiError> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if limit == 0 || limit > MAX_PAGE_SIZE {
+        return Err(ApiError::ValidationFailed(format!("limit must be between 1 and {}", MAX_PAGE_SIZE)));
+    }
+
+    let total = events.len();
+    let events = events.into_iter().skip(offset.unwrap_or(0)).take(limit).collect();
+
+    Ok(EventPage { events, total })
+}
+
+/// Status transitions of one event, oldest first. Admin-only since entries
+/// carry client addresses. 404 if the event doesn't exist.
+async fn get_event_history(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(title): Path<String>,
+) -> Result<Response, ApiError> {
+    require_admin(&state.sessions, &jar)?;
+
+    if !load_events("events.json").iter().any(|e| e.title == title) {
+        return Err(ApiError::EventNotFound(title));
+    }
+    Ok(bounded_json(&history::for_event(&title), state.max_response_bytes))
+}
+
+/// The events immediately before and after `title` by date (title as
+/// tiebreak), for prev/next navigation. Either side is null at the ends.
+async fn get_event_siblings(
+    Path(title): Path<String>
+) -> Result<Json<Siblings>, ApiError> {
+    let mut events = load_events("events.json");
+    sort_events(&mut events, SortKey::Date, SortOrder::Asc);
+
+    let Some(index) = events.iter().position(|e| e.title == title) else {
+        return Err(ApiError::EventNotFound(title));
+    };
+
+    let to_ref = |e: &Event| EventRef { title: e.title.clone(), status: e.status.clone() };
+    Ok(Json(Siblings {
+        previous: index.checked_sub(1).map(|i| to_ref(&events[i])),
+        next: events.get(index + 1).map(to_ref),
+    }))
+}
+
+/// Cheap "has anything changed?" check for pollers: an opaque token built from
+/// the events file's modification time and size, without reading the file.
+async fn get_change_token() -> Json<ChangeToken> {
+    let (modified, len) = fs::metadata("events.json")
+        .map(|m| {
+            let modified = m.modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos())
+                .unwrap_or(0);
+            (modified, m.len())
+        })
+        .unwrap_or((0, 0));
+
+    Json(ChangeToken { token: format!("{:x}-{:x}", modified, len) })
+}
+
+async fn get_config(State(state): State<AppState>) -> Json<PublicConfig> {
+    Json(PublicConfig::clone(&state.public_config))
+}
+
+/// Number of events in each status, optionally limited to `?from=`/`?to=`
+/// (inclusive, YYYY-MM-DD). Every workflow status is present, with 0 if unused.
+async fn get_status_counts(QueryParams(query): QueryParams<StatsQuery>) -> Json<StatusCounts> {
+    let filter = EventFilter { from: query.from, to: query.to, ..Default::default() };
+
+    let mut stats = StatusCounts {
+        total: 0,
+        counts: WORKFLOW_STATUSES.iter().map(|s| (s.to_string(), 0)).collect(),
+    };
+    for event in load_events("events.json").iter().filter(|e| filter.matches(e)) {
+        stats.total += 1;
+        *stats.counts.entry(event.status.clone()).or_default() += 1;
+    }
+
+    Json(stats)
+}
+
+/// How much work remains: every event not yet `Complete`, split by whether
+/// it is sitting in an error state.
+async fn get_backlog() -> Json<Backlog> {
+    let events = load_events("events.json");
+
+    let mut backlog = Backlog { total: 0, in_error: 0, not_in_error: 0 };
+    for event in events.iter().filter(|e| e.status != "Complete") {
+        backlog.total += 1;
+        if is_error_status(&event.status) {
+            backlog.in_error += 1;
+        } else {
+            backlog.not_in_error += 1;
+        }
+    }
+
+    Json(backlog)
+}
+
+const DEFAULT_WEEKS: u32 = 12;
+const MAX_WEEKS: u32 = 520;
+
+/// Per ISO week over the last `?weeks=N` weeks (default 12, current week
+/// included): how many events started that week and how many of those are
+/// now Complete. Weeks without events are reported with zero counts.
+async fn get_weekly_stats(
+    QueryParams(query): QueryParams<WeeklyQuery>
+) -> Result<Json<Vec<WeekStats>>, ApiError> {
+    let weeks = query.weeks.unwrap_or(DEFAULT_WEEKS);
+    if weeks == 0 || weeks > MAX_WEEKS {
+        return Err(ApiError::ValidationFailed(format!("weeks must be between 1 and {}", MAX_WEEKS)));
+    }
+
+    let week_start = |d: NaiveDate| d - chrono::Duration::days(d.weekday().num_days_from_monday() as i64);
+    let current = week_start(Utc::now().date_naive());
+    let mut stats: Vec<WeekStats> = (0..weeks as i64).rev()
+        .map(|i| current - chrono::Duration::weeks(i))
+        .map(|start| WeekStats {
+            week_start: start,
+            iso_week: format!("{}-W{:02}", start.iso_week().year(), start.iso_week().week()),
+            started: 0,
+            currently_complete: 0,
+        })
+        .collect();
+
+    let first = stats[0].week_start;
+    for event in load_events("events.json") {
+        let Ok(date) = NaiveDate::parse_from_str(&event.date, "%Y-%m-%d") else { continue };
+        let start = week_start(date);
+        if start < first || start > current {
+            continue;
+        }
+        let week = &mut stats[((start - first).num_days() / 7) as usize];
+        week.started += 1;
+        if event.status == "Complete" {
+            week.currently_complete += 1;
+        }
+    }
+
+    Ok(Json(stats))
+}
+
+/// Distinct statuses currently used by at least one event, in workflow order.
+/// Statuses outside the known workflow are appended alphabetically.
+async fn get_states_in_use() -> Json<Vec<String>> {
+    let events = load_events("events.json");
+
+    let distinct: BTreeSet<String> = events.into_iter().map(|e| e.status).collect();
+    let mut in_use: Vec<String> = distinct.into_iter().collect();
+    // Stable sort keeps unknown statuses in their alphabetical (BTreeSet) order
+    in_use.sort_by_key(|status| {
+        WORKFLOW_STATUSES.iter().position(|s| s == status).unwrap_or(WORKFLOW_STATUSES.len())
+    });
+
+    Json(in_use)
+}
+
+/// Compares the local events.json against the GitHub mirror so drift can be
+/// inspected before re-syncing.
+async fn github_diff(State(state): State<AppState>, jar: CookieJar) -> Result<Response, ApiError> {
+    require_admin(&state.sessions, &jar)?;
+    if !github::is_configured() {
+        return Err(ApiError::MirrorNotConfigured);
+    }
+
+    let mirror = github::fetch_mirror().await
+        .map_err(|e| ApiError::MirrorUnavailable(format!("Failed to fetch GitHub mirror: {}", e)))?;
+    let remote: Vec<Event> = serde_json::from_str(&mirror)
+        .map_err(|e| ApiError::MirrorUnavailable(format!("GitHub mirror is not valid events JSON: {}", e)))?;
+    let local = load_events("events.json");
+
+    let remote_status: HashMap<&str, &str> = remote.iter()
+        .map(|e| (e.title.as_str(), e.status.as_str()))
+        .collect();
+    let local_titles: BTreeSet<&str> = local.iter().map(|e| e.title.as_str()).collect();
+
+    let mut diff = MirrorDiff { only_local: vec![], only_github: vec![], status_mismatched: vec![] };
+    for event in &local {
+        match remote_status.get(event.title.as_str()) {
+            None => diff.only_local.push(event.title.clone()),
+            Some(&status) if status != event.status => diff.status_mismatched.push(StatusMismatch {
+                title: event.title.clone(),
+                local_status: event.status.clone(),
+                github_status: status.to_string(),
+            }),
+            Some(_) => {}
+        }
+    }
+    diff.only_github = remote.iter()
+        .filter(|e| !local_titles.contains(e.title.as_str()))
+        .map(|e| e.title.clone())
+        .collect();
+
+    Ok(bounded_json(&diff, state.max_response_bytes))
 }
 
 async fn login_handler(
+    State(state): State<AppState>,
     jar: CookieJar, 
-    ExtractJson(payload): ExtractJson<LoginPayload>
-) -> impl IntoResponse {
-    // ADMIN_PASSWORD is guaranteed to be set (checked in main)
-    let actual_pass = env::var("ADMIN_PASSWORD").unwrap();
-
-    if payload.password == actual_pass {
-        let cookie = Cookie::build("session", "admin_authorized")
+    JsonBody(payload): JsonBody<LoginPayload>
+) -> Result<(CookieJar, Json<String>), ApiError> {
+    if verify_admin_password(&payload.password) {
+        let cookie = Cookie::build("session", state.sessions.create())
             .path("/")
-            .http_only(false)
+            .http_only(true)
             .same_site(SameSite::Lax)
             .finish();
         
-        (jar.add(cookie), Json("Login Successful".to_string()))
+        Ok((jar.add(cookie), Json("Login Successful".to_string())))
     } else {
-        (jar, Json("Invalid Password".to_string()))
+        Err(ApiError::InvalidCredentials)
+    }
+}
+
+/// Ends the caller's session. Always 200, so the UI can call it whether or
+/// not the session is still alive.
+async fn logout_handler(State(state): State<AppState>, jar: CookieJar) -> (CookieJar, Json<String>) {
+    if let Some(cookie) = jar.get("session") {
+        state.sessions.remove(cookie.value());
+    }
+    let cookie = Cookie::build("session", "").path("/").finish();
+    (jar.remove(cookie), Json("Logged Out".to_string()))
+}
+
+/// Reports whether the caller's session is valid. Always 200 so the UI can
+/// check without provoking a 401.
+async fn me_handler(State(state): State<AppState>, jar: CookieJar) -> Json<SessionInfo> {
+    let authenticated = is_authorized(&state.sessions, &jar);
+    Json(SessionInfo {
+        authenticated,
+        // There is a single shared admin login, so no per-user name
+        role: authenticated.then(|| "admin".to_string()),
+        username: None,
+    })
+}
+
+/// Checks a login attempt against ADMIN_PASSWORD_HASH, or the plaintext
+/// ADMIN_PASSWORD when no hash is configured. One of them is guaranteed
+/// to be set (checked in main).
+fn verify_admin_password(candidate: &str) -> bool {
+    match env::var("ADMIN_PASSWORD_HASH") {
+        Ok(hash) => PasswordHash::new(&hash)
+            .is_ok_and(|parsed| Argon2::default().verify_password(candidate.as_bytes(), &parsed).is_ok()),
+        Err(_) => env::var("ADMIN_PASSWORD").is_ok_and(|actual| candidate == actual),
     }
 }
 
+fn hash_password(password: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .expect("Failed to hash password")
+        .to_string()
+}
+
+/// True if the session cookie holds a token for a live session.
+fn is_authorized(sessions: &SessionStore, jar: &CookieJar) -> bool {
+    jar.get("session").is_some_and(|c| sessions.is_valid(c.value()))
+}
+
+fn require_admin(sessions: &SessionStore, jar: &CookieJar) -> Result<(), ApiError> {
+    if is_authorized(sessions, jar) { Ok(()) } else { Err(ApiError::Unauthorized) }
+}
+
+/// Rejects statuses that aren't part of the workflow, listing the valid ones.
+fn validate_status(status: &str) -> Result<(), ApiError> {
+    if WORKFLOW_STATUSES.contains(&status) {
+        return Ok(());
+    }
+    Err(ApiError::ValidationFailed(format!(
+        "Unknown status \"{}\"; expected one of: {}", status, WORKFLOW_STATUSES.join(", ")
+    )))
+}
+
+/// Like `load_events`, but reports a missing or unparseable file instead of
+/// treating it as empty.
+fn read_events(path: &str) -> Result<Vec<Event>, String> {
+    let data = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    serde_json::from_str(&data).map_err(|e| format!("Cannot parse {}: {}", path, e))
+}
+
+fn load_events(path: &str) -> Vec<Event> {
+    let data = fs::read_to_string(path).unwrap_or_else(|_| "[]".to_string());
+    serde_json::from_str(&data).unwrap_or(vec![])
+}
+
+/// Writes the events locally and queues them for the GitHub mirror. Nothing
+/// is queued if the local write fails.
+fn save_events(github: &GitHubSync, path: &str, events: &[Event]) -> Result<(), ApiError> {
+    let new_json = serde_json::to_string_pretty(events).unwrap();
+    fs::write(path, &new_json)
+        .map_err(|e| ApiError::Internal(format!("Failed to write {}: {}", path, e)))?;
+    github.queue(new_json);
+    Ok(())
+}
+
+/// Records `changes` in the history log, then saves `events`. The history
+/// append is rolled back if the save fails, so the log never describes a
+/// change that didn't happen. Subscribers are only notified once both succeed.
+/// Callers must hold `events_lock` from loading `events` until this returns.
+fn save_status_changes(state: &AppState, path: &str, events: &[Event], changes: &[StatusChange]) -> Result<(), ApiError> {
+    let history_len = history::append(changes)?;
+    if let Err(e) = save_events(&state.github, path, events) {
+        if let Err(rollback) = history::truncate_to(history_len) {
+            tracing::error!("Failed to roll back history after a failed save: {}", rollback);
+        }
+        return Err(e);
+    }
+    publish_status_changes(state, changes);
+    Ok(())
+}
+
+/// Sets one event's status and returns the event. 404 if the title is
+/// unknown, 409 if the event is blocked.
 async fn update_event(
+    State(state): State<AppState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
     jar: CookieJar, 
-    ExtractJson(payload): ExtractJson<UpdatePayload>
-) -> impl IntoResponse {
+    JsonBody(payload): JsonBody<UpdatePayload>
+) -> Result<Json<Event>, ApiError> {
     
     // Check for cookie
-    if jar.get("session").map(|c| c.value()) != Some("admin_authorized") {
-        return (StatusCode::UNAUTHORIZED, Json("Please Log In First".to_string()));
-    }
+    require_admin(&state.sessions, &jar)?;
+    validate_status(&payload.new_status)?;
 
     let path = "events.json";
-    let data = fs::read_to_string(path).unwrap_or_else(|_| "[]".to_string());
-    let mut events: Vec<Event> = serde_json::from_str(&data).unwrap_or(vec![]);
+    let _events_guard = state.events_lock.lock().unwrap();
+    let mut events = load_events(path);
 
-    let mut updated = false;
-    for event in &mut events {
-        if event.title == payload.title {
-            event.status = payload.new_status.clone();
-            updated = true;
-        }
-    }
+    let (updated, old_status) = apply_status_update(&mut events, &payload.title, &payload.new_status)?;
+    // No-op updates don't rewrite or push the file
+    let Some(old_status) = old_status else {
+        return Ok(Json(updated));
+    };
 
-    if updated {
-        let new_json = serde_json::to_string_pretty(&events).unwrap();
-        fs::write(path, &new_json).expect("Failed to write local file");
+    let changes = [StatusChange::new(&updated.title, &old_status, &updated.status, client.ip())];
+    save_status_changes(&state, path, &events, &changes)?;
+    webhook::notify(&changes, &events);
 
-        tokio::spawn(async move {
-            if let Err(e) = push_to_github(new_json).await {
-                eprintln!("Failed to sync with GitHub: {}", e);
-            }
-        });
+    Ok(Json(updated))
+}
+
+/// Sets the status of the event titled `title`. Returns the event as it now
+/// stands, plus its previous status if the status actually changed.
+fn apply_status_update(events: &mut [Event], title: &str, new_status: &str) -> Result<(Event, Option<String>), ApiError> {
+    let Some(event) = events.iter_mut().find(|e| e.title == title) else {
+        return Err(ApiError::EventNotFound(title.to_string()));
+    };
+
+    if event.blocked {
+        return Err(ApiError::EventBlocked { title: event.title.clone(), reason: event.blocked_reason.clone() });
     }
 
-    (StatusCode::OK, Json("Updated".to_string()))
+    if event.status == new_status {
+        return Ok((event.clone(), None));
+    }
+
+    let old_status = std::mem::replace(&mut event.status, new_status.to_string());
+    Ok((event.clone(), Some(old_status)))
 }
 
 async fn bulk_update_events(
+    State(state): State<AppState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
     jar: CookieJar,
-    ExtractJson(payload): ExtractJson<BulkUpdatePayload>
-) -> impl IntoResponse {
+    JsonBody(payload): JsonBody<BulkUpdatePayload>
+) -> Result<Json<BulkUpdateResult>, ApiError> {
     
     // Check for cookie
-    if jar.get("session").map(|c| c.value()) != Some("admin_authorized") {
-        return (StatusCode::UNAUTHORIZED, Json("Please Log In First".to_string()));
+    require_admin(&state.sessions, &jar)?;
+
+    if payload.titles.len() > state.max_batch_size {
+        return Err(ApiError::ValidationFailed(format!("At most {} titles per request", state.max_batch_size)));
     }
+    validate_status(&payload.new_status)?;
 
     let path = "events.json";
-    let data = fs::read_to_string(path).unwrap_or_else(|_| "[]".to_string());
-    let mut events: Vec<Event> = serde_json::from_str(&data).unwrap_or(vec![]);
+    let _events_guard = state.events_lock.lock().unwrap();
+    let mut events = load_events(path);
 
-    let mut updated_count = 0;
-    for event in &mut events {
-        if payload.titles.contains(&event.title) {
+    let mut result = BulkUpdateResult { updated: 0, not_found: Vec::new(), blocked: Vec::new() };
+    let mut changes = Vec::new();
+    for title in &payload.titles {
+        let Some(event) = events.iter_mut().find(|e| &e.title == title) else {
+            result.not_found.push(title.clone());
+            continue;
+        };
+        // Blocked events are left untouched
+        if event.blocked {
+            result.blocked.push(title.clone());
+            continue;
+        }
+        if event.status != payload.new_status {
+            changes.push(StatusChange::new(title, &event.status, &payload.new_status, client.ip()));
             event.status = payload.new_status.clone();
-            updated_count += 1;
+            result.updated += 1;
         }
     }
 
-    if updated_count > 0 {
-        let new_json = serde_json::to_string_pretty(&events).unwrap();
-        fs::write(path, &new_json).expect("Failed to write local file");
+    if result.updated > 0 {
+        save_status_changes(&state, path, &events, &changes)?;
+        webhook::notify(&changes, &events);
+    }
 
-        tokio::spawn(async move {
-            if let Err(e) = push_to_github(new_json).await {
-                eprintln!("Failed to sync with GitHub: {}", e);
-            }
-        });
+    Ok(Json(result))
+}
+
+/// Error statuses and the status each one is recovered to.
+const ERROR_RECOVERY: [(&str, &str); 2] = [
+    ("Step 1 Error", "Process Step 1"),
+    ("Step 2 Error", "Process Step 2"),
+];
+
+fn is_error_status(status: &str) -> bool {
+    ERROR_RECOVERY.iter().any(|(error, _)| *error == status)
+}
+
+/// Moves every errored, unblocked event back to the processing status of
+/// the step that failed, in a single write and mirror push.
+async fn recover_errors(
+    State(state): State<AppState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    jar: CookieJar,
+) -> Result<Json<RecoverResult>, ApiError> {
+    require_admin(&state.sessions, &jar)?;
+
+    let path = "events.json";
+    let _events_guard = state.events_lock.lock().unwrap();
+    let mut events = load_events(path);
+
+    let mut recovered = 0;
+    let mut changes = Vec::new();
+    for event in events.iter_mut().filter(|e| !e.blocked) {
+        if let Some((_, target)) = ERROR_RECOVERY.iter().find(|(error, _)| *error == event.status) {
+            changes.push(StatusChange::new(&event.title, &event.status, target, client.ip()));
+            event.status = target.to_string();
+            recovered += 1;
+        }
     }
 
-    (StatusCode::OK, Json(format!("Updated {} events", updated_count)))
+    if recovered > 0 {
+        save_status_changes(&state, path, &events, &changes)?;
+    }
+
+    Ok(Json(RecoverResult { recovered }))
+}
+
+async fn block_event(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(title): Path<String>,
+    JsonBody(payload): JsonBody<BlockPayload>
+) -> Result<Json<String>, ApiError> {
+    set_blocked(&state, jar, title, true, payload.reason)
 }
 
-async fn push_to_github(json_content: String) -> Result<(), Box<dyn std::error::Error>> {
-    let client = reqwest::Client::new();
-    let token = env::var("GITHUB_TOKEN")?; 
-    let owner = env::var("REPO_OWNER")?; 
-    let repo = env::var("REPO_NAME")?;   
-    let file_path = "events.json";       
-    let url = format!("https://api.github.com/repos/{}/{}/contents/{}", owner, repo, file_path);
+async fn unblock_event(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(title): Path<String>,
+) -> Result<Json<String>, ApiError> {
+    set_blocked(&state, jar, title, false, None)
+}
 
-    let resp = client.get(&url)
-        .header("User-Agent", "rust-app")
-        .header("Authorization", format!("Bearer {}", token))
-        .send().await?.json::<GitHubFileResponse>().await?;
+fn set_blocked(state: &AppState, jar: CookieJar, title: String, blocked: bool, reason: Option<String>) -> Result<Json<String>, ApiError> {
+    require_admin(&state.sessions, &jar)?;
 
-    let encoded_content = general_purpose::STANDARD.encode(json_content);
+    let path = "events.json";
+    let _events_guard = state.events_lock.lock().unwrap();
+    let mut events = load_events(path);
 
-    let body = GitHubUpdatePayload {
-        message: "Update status via Web Dashboard".to_string(),
-        content: encoded_content,
-        sha: resp.sha,
+    let Some(event) = events.iter_mut().find(|e| e.title == title) else {
+        return Err(ApiError::EventNotFound(title));
     };
+    // Re-blocking with the same reason (or re-unblocking) doesn't rewrite or push the file
+    if event.blocked != blocked || event.blocked_reason != reason {
+        event.blocked = blocked;
+        event.blocked_reason = reason;
+        save_events(&state.github, path, &events)?;
+    }
 
-    client.put(&url)
-        .header("User-Agent", "rust-app")
-        .header("Authorization", format!("Bearer {}", token))
-        .json(&body)
-        .send().await?;
+    let verb = if blocked { "Blocked" } else { "Unblocked" };
+    Ok(Json(format!("{} event {}", verb, title)))
+}
 
-    Ok(())
-}
\ No newline at end of file
+/// Corrects an event's date, url and/or description, returning the updated event.
+async fn patch_event(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(title): Path<String>,
+    JsonBody(payload): JsonBody<EventPatch>
+) -> Result<Json<Event>, ApiError> {
+    require_admin(&state.sessions, &jar)?;
+
+    let path = "events.json";
+    let _events_guard = state.events_lock.lock().unwrap();
+    let mut events = load_events(path);
+
+    let Some(event) = events.iter_mut().find(|e| e.title == title) else {
+        return Err(ApiError::EventNotFound(title));
+    };
+    let original = event.clone();
+    if let Some(date) = payload.date {
+        event.date = date.format("%Y-%m-%d").to_string();
+    }
+    if let Some(url) = payload.url {
+        event.url = url;
+    }
+    if let Some(description) = payload.description {
+        event.description = description;
+    }
+    if let Some(deadline) = payload.deadline {
+        event.deadline = Some(deadline);
+    }
+    let updated = event.clone();
+
+    // No-op patches don't rewrite or push the file
+    if updated != original {
+        save_events(&state.github, path, &events)?;
+    }
+
+    Ok(Json(updated))
+}
+
+/// Removes an event created by mistake or imported with a bad title.
+async fn delete_event(
+    State(state): State<AppState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    jar: CookieJar,
+    Path(title): Path<String>,
+) -> Result<Json<String>, ApiError> {
+    require_admin(&state.sessions, &jar)?;
+
+    let path = "events.json";
+    let _events_guard = state.events_lock.lock().unwrap();
+    let mut events = load_events(path);
+
+    let removed = remove_event(&mut events, &title)?;
+    // Audited like a status change; also cuts the title's history off so a
+    // later event with the same title starts with a clean one
+    let changes = [StatusChange::new(&removed.title, &removed.status, history::DELETED, client.ip())];
+    save_status_changes(&state, path, &events, &changes)?;
+
+    Ok(Json(format!("Deleted event {}", removed.title)))
+}
+
+/// Removes the event titled `title`, keeping the order of the rest.
+fn remove_event(events: &mut Vec<Event>, title: &str) -> Result<Event, ApiError> {
+    let Some(index) = events.iter().position(|e| e.title == title) else {
+        return Err(ApiError::EventNotFound(title.to_string()));
+    };
+    Ok(events.remove(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+    use tower::ServiceExt;
+
+    fn event(title: &str, status: &str, blocked: bool) -> Event {
+        Event {
+            title: title.to_string(),
+            date: "2024-01-01".to_string(),
+            url: String::new(),
+            status: status.to_string(),
+            description: String::new(),
+            blocked,
+            blocked_reason: None,
+            deadline: None,
+        }
+    }
+
+    fn jar_with_session(token: &str) -> CookieJar {
+        CookieJar::new().add(Cookie::new("session", token.to_string()))
+    }
+
+    #[test]
+    fn require_admin_rejects_session_after_logout() {
+        let sessions = SessionStore::new(Duration::from_secs(60));
+        let token = sessions.create();
+        let jar = jar_with_session(&token);
+        assert!(require_admin(&sessions, &jar).is_ok());
+
+        sessions.remove(&token);
+        assert!(!sessions.is_valid(&token));
+        let err = require_admin(&sessions, &jar).unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn require_admin_rejects_missing_cookie() {
+        let sessions = SessionStore::new(Duration::from_secs(60));
+        sessions.create();
+        assert!(require_admin(&sessions, &CookieJar::new()).is_err());
+    }
+
+    #[test]
+    fn status_update_of_unknown_event_is_not_found() {
+        let mut events = vec![event("100", "Not Yet Started", false)];
+        let err = apply_status_update(&mut events, "200", "Process Step 1").unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn status_update_of_blocked_event_conflicts() {
+        let mut events = vec![event("100", "Not Yet Started", true)];
+        let err = apply_status_update(&mut events, "100", "Process Step 1").unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::CONFLICT);
+        assert_eq!(events[0].status, "Not Yet Started");
+    }
+
+    #[test]
+    fn status_update_changes_status_and_reports_old_one() {
+        let mut events = vec![event("100", "Not Yet Started", false)];
+        let (updated, old_status) = apply_status_update(&mut events, "100", "Process Step 1").unwrap();
+        assert_eq!(updated.status, "Process Step 1");
+        assert_eq!(events[0].status, "Process Step 1");
+        assert_eq!(old_status.as_deref(), Some("Not Yet Started"));
+    }
+
+    #[test]
+    fn status_update_to_same_status_is_a_no_op() {
+        let mut events = vec![event("100", "Not Yet Started", false)];
+        let (_, old_status) = apply_status_update(&mut events, "100", "Not Yet Started").unwrap();
+        assert!(old_status.is_none());
+    }
+
+    #[test]
+    fn remove_event_drops_only_that_event() {
+        let mut events = vec![
+            event("100", "Complete", false),
+            event("101", "Complete", false),
+            event("102", "Complete", false),
+        ];
+        let removed = remove_event(&mut events, "101").unwrap();
+        assert_eq!(removed.title, "101");
+        let titles: Vec<&str> = events.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, ["100", "102"]);
+    }
+
+    #[test]
+    fn remove_unknown_event_is_not_found() {
+        let mut events = vec![event("100", "Complete", false)];
+        let err = remove_event(&mut events, "200").unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::NOT_FOUND);
+        assert_eq!(events.len(), 1);
+    }
+
+    #[test]
+    fn overdue_events_skip_complete_and_sort_most_overdue_first() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let with_deadline = |title: &str, status: &str, deadline: Option<NaiveDate>| Event {
+            deadline,
+            ..event(title, status, false)
+        };
+        let events = vec![
+            with_deadline("100", "Process Step 1", NaiveDate::from_ymd_opt(2024, 3, 8)),
+            with_deadline("101", "Complete", NaiveDate::from_ymd_opt(2024, 3, 1)),
+            with_deadline("102", "Process Step 2", NaiveDate::from_ymd_opt(2024, 3, 1)),
+            with_deadline("103", "Process Step 2", Some(today)),
+            with_deadline("104", "Process Step 2", None),
+        ];
+
+        let overdue = overdue_events(events, today);
+        let summary: Vec<(&str, i64)> = overdue.iter().map(|o| (o.event.title.as_str(), o.days_overdue)).collect();
+        assert_eq!(summary, [("102", 9), ("100", 2)]);
+    }
+
+    /// A router echoing a `JsonBody` back, behind the same decompression stack as the app.
+    fn echo_app() -> Router {
+        let echo = post(|JsonBody(value): JsonBody<serde_json::Value>| async move { Json(value) });
+        with_request_decompression(Router::new().route("/", echo))
+    }
+
+    fn json_request(body: Vec<u8>, encoding: Option<&str>) -> Request<Body> {
+        let mut request = Request::post("/").header(header::CONTENT_TYPE, "application/json");
+        if let Some(encoding) = encoding {
+            request = request.header(header::CONTENT_ENCODING, encoding);
+        }
+        request.body(Body::from(body)).unwrap()
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    async fn error_code(response: Response) -> String {
+        let mut body = response.into_body();
+        let mut bytes = Vec::new();
+        while let Some(chunk) = body.data().await {
+            bytes.extend_from_slice(&chunk.unwrap());
+        }
+        let error: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        error["code"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn gzip_body_is_decompressed() {
+        let response = echo_app().oneshot(json_request(gzip(br#"{"q":"1417"}"#), Some("gzip"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn unsupported_encoding_is_an_api_error() {
+        let response = echo_app().oneshot(json_request(b"{}".to_vec(), Some("br"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(error_code(response).await, "UNSUPPORTED_MEDIA_TYPE");
+    }
+
+    #[tokio::test]
+    async fn gzip_body_inflating_past_the_body_limit_is_rejected() {
+        // Compresses to a few KB but inflates past axum's 2 MB default limit
+        let body = format!(r#"{{"q":"{}"}}"#, "x".repeat(3 * 1024 * 1024));
+        let response = echo_app().oneshot(json_request(gzip(body.as_bytes()), Some("gzip"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(error_code(response).await, "VALIDATION_FAILED");
+    }
+
+    /// Runs `body` through the `JsonBody` extractor as a handler would see it.
+    async fn extract_json<T: DeserializeOwned>(body: &str) -> Result<T, ApiError> {
+        let request = Request::post("/")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        JsonBody::<T>::from_request(request, &()).await.map(|JsonBody(value)| value)
+    }
+
+    async fn assert_unknown_field_rejected<T: DeserializeOwned>(body: &str) {
+        let err = extract_json::<T>(body).await.err().expect("unknown field was accepted");
+        assert_eq!(err.code(), "VALIDATION_FAILED");
+        assert_eq!(err.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn payloads_with_unknown_fields_are_validation_failures() {
+        assert_unknown_field_rejected::<UpdatePayload>(r#"{"title":"100","new_status":"Complete","extra":1}"#).await;
+        assert_unknown_field_rejected::<BulkUpdatePayload>(r#"{"titles":["100"],"new_status":"Complete","extra":1}"#).await;
+        assert_unknown_field_rejected::<LoginPayload>(r#"{"password":"pw","extra":1}"#).await;
+        assert_unknown_field_rejected::<EventPatch>(r#"{"description":"x","extra":1}"#).await;
+        assert_unknown_field_rejected::<BlockPayload>(r#"{"reason":"x","extra":1}"#).await;
+        assert_unknown_field_rejected::<SearchPayload>(r#"{"q":"1417","status":"Complete"}"#).await;
+    }
+
+    #[tokio::test]
+    async fn payloads_with_known_fields_are_accepted() {
+        assert!(extract_json::<UpdatePayload>(r#"{"title":"100","new_status":"Complete"}"#).await.is_ok());
+        assert!(extract_json::<BulkUpdatePayload>(r#"{"titles":["100"],"new_status":"Complete"}"#).await.is_ok());
+        assert!(extract_json::<LoginPayload>(r#"{"password":"pw"}"#).await.is_ok());
+        assert!(extract_json::<EventPatch>(r#"{"description":"x","deadline":"2024-03-01"}"#).await.is_ok());
+        assert!(extract_json::<BlockPayload>(r#"{"reason":"x"}"#).await.is_ok());
+        assert!(extract_json::<SearchPayload>(r#"{"q":"1417","statuses":["Complete"],"limit":10}"#).await.is_ok());
+    }
+
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("line\r\nbreak"), "\"line\r\nbreak\"");
+    }
+}
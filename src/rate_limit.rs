@@ -0,0 +1,183 @@
+use axum::{
+    extract::{ConnectInfo, State},
+    http::{Method, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::cookie::CookieJar;
+use crate::{errors::ApiError, sessions::SessionStore};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+// Once this many clients are tracked, fully refilled buckets are dropped.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Who a bucket belongs to. Requests carrying a valid admin session share
+/// that session's bucket, so admins behind one NAT or proxy don't throttle
+/// each other; everything else is limited per IP.
+#[derive(PartialEq, Eq, Hash, Debug)]
+enum ClientKey {
+    Session(String),
+    Ip(IpAddr),
+}
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Per-client token bucket: `capacity` requests, refilled at
+/// `capacity` tokens per minute.
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    sessions: Arc<SessionStore>,
+    buckets: Mutex<HashMap<ClientKey, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn per_minute(capacity: u32, sessions: Arc<SessionStore>) -> Self {
+        RateLimiter {
+            capacity: capacity as f64,
+            refill_per_sec: capacity as f64 / 60.0,
+            sessions,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The session token if `req` carries a valid one, otherwise the peer IP.
+    /// Unknown tokens fall back to the IP so made-up cookies can't dodge the limit.
+    fn client_key<B>(&self, req: &Request<B>, addr: SocketAddr) -> ClientKey {
+        match CookieJar::from_headers(req.headers()).get("session") {
+            Some(cookie) if self.sessions.is_valid(cookie.value()) => ClientKey::Session(cookie.value().to_string()),
+            _ => ClientKey::Ip(addr.ip()),
+        }
+    }
+
+    /// Takes one token for `client`, or returns the whole seconds to wait
+    /// until one is available.
+    fn try_acquire(&self, client: ClientKey) -> Result<(), u64> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() > PRUNE_THRESHOLD {
+            let (capacity, rate) = (self.capacity, self.refill_per_sec);
+            buckets.retain(|_, b| b.tokens + now.duration_since(b.last_refill).as_secs_f64() * rate < capacity);
+        }
+
+        let bucket = buckets.entry(client).or_insert(Bucket { tokens: self.capacity, last_refill: now });
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - bucket.tokens) / self.refill_per_sec).ceil() as u64)
+        }
+    }
+}
+
+/// Middleware limiting POST/PATCH/PUT/DELETE requests per admin session,
+/// or per client IP for requests without one.
+/// Reads (GET/HEAD/OPTIONS) are never limited.
+pub async fn limit_mutations<B>(
+    State(limiter): State<Arc<RateLimiter>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    if matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(req).await;
+    }
+
+    match limiter.try_acquire(limiter.client_key(&req, addr)) {
+        Ok(()) => next.run(req).await,
+        Err(retry_after_secs) => ApiError::RateLimited { retry_after_secs }.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{net::Ipv4Addr, thread, time::Duration};
+
+    const ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 4000);
+
+    fn limiter(capacity: u32) -> RateLimiter {
+        RateLimiter::per_minute(capacity, Arc::new(SessionStore::new(Duration::from_secs(60))))
+    }
+
+    fn ip(last: u8) -> ClientKey {
+        ClientKey::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, last)))
+    }
+
+    fn request_with_cookie(cookie: Option<&str>) -> Request<()> {
+        let mut request = Request::post("/api/events");
+        if let Some(cookie) = cookie {
+            request = request.header("cookie", cookie);
+        }
+        request.body(()).unwrap()
+    }
+
+    #[test]
+    fn exhausted_bucket_reports_retry_delay() {
+        let limiter = limiter(2);
+        assert!(limiter.try_acquire(ip(1)).is_ok());
+        assert!(limiter.try_acquire(ip(1)).is_ok());
+        // Two tokens per minute refill one token every 30 seconds
+        assert_eq!(limiter.try_acquire(ip(1)), Err(30));
+    }
+
+    #[test]
+    fn bucket_refills_over_time() {
+        // Ten tokens per second
+        let limiter = limiter(600);
+        while limiter.try_acquire(ip(1)).is_ok() {}
+
+        thread::sleep(Duration::from_millis(150));
+        assert!(limiter.try_acquire(ip(1)).is_ok());
+    }
+
+    #[test]
+    fn session_and_ip_get_separate_buckets() {
+        let limiter = limiter(1);
+        let session = ClientKey::Session("token".to_string());
+        assert!(limiter.try_acquire(session).is_ok());
+        assert!(limiter.try_acquire(ip(1)).is_ok());
+        assert!(limiter.try_acquire(ClientKey::Session("token".to_string())).is_err());
+        assert!(limiter.try_acquire(ip(1)).is_err());
+    }
+
+    #[test]
+    fn client_key_uses_valid_session_else_ip() {
+        let limiter = limiter(60);
+        let token = limiter.sessions.create();
+
+        let with_session = request_with_cookie(Some(&format!("session={}", token)));
+        assert_eq!(limiter.client_key(&with_session, ADDR), ClientKey::Session(token));
+
+        let with_unknown_session = request_with_cookie(Some("session=made-up"));
+        assert_eq!(limiter.client_key(&with_unknown_session, ADDR), ClientKey::Ip(ADDR.ip()));
+
+        assert_eq!(limiter.client_key(&request_with_cookie(None), ADDR), ClientKey::Ip(ADDR.ip()));
+    }
+
+    #[test]
+    fn refilled_buckets_are_pruned_past_threshold() {
+        // Refills a spent token in well under a millisecond
+        let limiter = limiter(6_000_000);
+        for n in 0..=PRUNE_THRESHOLD as u32 {
+            limiter.try_acquire(ClientKey::Ip(IpAddr::V4(Ipv4Addr::from(n)))).unwrap();
+        }
+        thread::sleep(Duration::from_millis(10));
+
+        limiter.try_acquire(ip(1)).unwrap();
+        assert_eq!(limiter.buckets.lock().unwrap().len(), 1);
+    }
+}
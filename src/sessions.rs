@@ -0,0 +1,74 @@
+use base64::{Engine as _, engine::general_purpose};
+use rand::RngCore;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Server-side admin sessions. The cookie only carries a random token;
+/// a token is valid while it is in the store and younger than `ttl`.
+pub struct SessionStore {
+    ttl: Duration,
+    tokens: Mutex<HashMap<String, Instant>>,
+}
+
+impl SessionStore {
+    pub fn new(ttl: Duration) -> Self {
+        SessionStore { ttl, tokens: Mutex::new(HashMap::new()) }
+    }
+
+    /// Creates a new session and returns its token.
+    pub fn create(&self) -> String {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let token = general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+
+        let now = Instant::now();
+        let mut tokens = self.tokens.lock().unwrap();
+        // Drop expired sessions so abandoned logins don't accumulate
+        tokens.retain(|_, created| now.duration_since(*created) < self.ttl);
+        tokens.insert(token.clone(), now);
+        token
+    }
+
+    /// True if `token` belongs to a session that hasn't expired.
+    pub fn is_valid(&self, token: &str) -> bool {
+        let mut tokens = self.tokens.lock().unwrap();
+        match tokens.get(token) {
+            Some(created) if created.elapsed() < self.ttl => true,
+            Some(_) => {
+                tokens.remove(token);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Ends the session for `token`, if there is one.
+    pub fn remove(&self, token: &str) {
+        self.tokens.lock().unwrap().remove(token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_is_valid_until_it_expires() {
+        let store = SessionStore::new(Duration::from_millis(50));
+        let token = store.create();
+        assert!(store.is_valid(&token));
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(!store.is_valid(&token));
+    }
+
+    #[test]
+    fn unknown_token_is_rejected() {
+        let store = SessionStore::new(Duration::from_secs(60));
+        store.create();
+        assert!(!store.is_valid("admin_authorized"));
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{history::StatusChange, Event};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::env;
+
+/// Statuses that trigger a notification to `ERROR_WEBHOOK_URL` when an event enters them.
+const NOTIFY_STATUSES: [&str; 2] = ["Step 1 Error", "Step 2 Error"];
+
+#[derive(Serialize)]
+struct Notification {
+    title: String,
+    new_status: String,
+    changed_at: DateTime<Utc>,
+    url: String,
+}
+
+/// POSTs one notification per change into a notify status, each on its own
+/// task. Best-effort: delivery failures are logged, never returned.
+pub fn notify(changes: &[StatusChange], events: &[Event]) {
+    let Ok(webhook_url) = env::var("ERROR_WEBHOOK_URL") else {
+        return;
+    };
+
+    for change in changes.iter().filter(|c| NOTIFY_STATUSES.contains(&c.new_status.as_str())) {
+        let notification = Notification {
+            title: change.title.clone(),
+            new_status: change.new_status.clone(),
+            changed_at: change.changed_at,
+            url: events.iter().find(|e| e.title == change.title).map(|e| e.url.clone()).unwrap_or_default(),
+        };
+        let webhook_url = webhook_url.clone();
+
+        tokio::spawn(async move {
+            let result = reqwest::Client::new()
+                .post(&webhook_url)
+                .json(&notification)
+                .send().await
+                .and_then(|resp| resp.error_for_status());
+            if let Err(e) = result {
+                tracing::error!("Failed to deliver error webhook for event {}: {}", notification.title, e);
+            }
+        });
+    }
+}